@@ -0,0 +1,114 @@
+use std::io::Write;
+
+use crate::result::{Error, Result};
+
+/// Pluggable encoding scheme used to turn typed keys/values into the bytes
+/// sled actually stores, and back.
+///
+/// [`Tree`](crate::Tree) and friends are generic over a `Codec` so callers can
+/// swap `bincode::config::standard()`'s varint layout for a fixed-width one
+/// (see [`OrderedKeyCodec`]) or for an entirely different wire format,
+/// without touching the rest of the API.
+#[cfg(not(feature = "serde"))]
+pub trait Codec {
+    fn encode_into<T: bincode::Encode>(value: &T, writer: &mut impl Write) -> Result<()>;
+    fn decode<'a, T: bincode::BorrowDecode<'a>>(bytes: &'a [u8]) -> Result<T>;
+}
+
+#[cfg(feature = "serde")]
+pub trait Codec {
+    fn encode_into<T: serde::Serialize>(value: &T, writer: &mut impl Write) -> Result<()>;
+    fn decode<'a, T: serde::Deserialize<'a>>(bytes: &'a [u8]) -> Result<T>;
+}
+
+/// The codec this crate used before it became pluggable: `bincode`'s standard
+/// configuration (varint integers, little-endian). Kept as the default type
+/// parameter so existing code keeps compiling unchanged.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BincodeStandard;
+
+#[cfg(not(feature = "serde"))]
+impl Codec for BincodeStandard {
+    #[inline]
+    fn encode_into<T: bincode::Encode>(value: &T, writer: &mut impl Write) -> Result<()> {
+        bincode::encode_into_std_write(value, writer, bincode::config::standard())
+            .map(|_| ())
+            .map_err(Error::EncodeError)
+    }
+
+    #[inline]
+    fn decode<'a, T: bincode::BorrowDecode<'a>>(bytes: &'a [u8]) -> Result<T> {
+        let (val, _) = bincode::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(Error::DecodeError)?;
+        Ok(val)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Codec for BincodeStandard {
+    #[inline]
+    fn encode_into<T: serde::Serialize>(value: &T, writer: &mut impl Write) -> Result<()> {
+        bincode::serde::encode_into_std_write(value, writer, bincode::config::standard())
+            .map(|_| ())
+            .map_err(Error::EncodeError)
+    }
+
+    #[inline]
+    fn decode<'a, T: serde::Deserialize<'a>>(bytes: &'a [u8]) -> Result<T> {
+        bincode::serde::decode_borrowed_from_slice(bytes, bincode::config::standard())
+            .map_err(Error::DecodeError)
+    }
+}
+
+/// A codec that encodes integer keys as big-endian, fixed-width bytes.
+///
+/// `bincode`'s standard varint encoding does not preserve the numeric
+/// ordering of the values it encodes, so [`Tree::range`](crate::Tree::range)
+/// and [`Tree::scan_prefix`](crate::Tree::scan_prefix) over
+/// [`BincodeStandard`]-encoded integer keys silently return entries in the
+/// wrong order. Use this codec for any entry whose key ordering matters.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OrderedKeyCodec;
+
+#[cfg(not(feature = "serde"))]
+impl Codec for OrderedKeyCodec {
+    #[inline]
+    fn encode_into<T: bincode::Encode>(value: &T, writer: &mut impl Write) -> Result<()> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_fixed_int_encoding();
+        bincode::encode_into_std_write(value, writer, config)
+            .map(|_| ())
+            .map_err(Error::EncodeError)
+    }
+
+    #[inline]
+    fn decode<'a, T: bincode::BorrowDecode<'a>>(bytes: &'a [u8]) -> Result<T> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_fixed_int_encoding();
+        let (val, _) = bincode::decode_from_slice(bytes, config).map_err(Error::DecodeError)?;
+        Ok(val)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Codec for OrderedKeyCodec {
+    #[inline]
+    fn encode_into<T: serde::Serialize>(value: &T, writer: &mut impl Write) -> Result<()> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_fixed_int_encoding();
+        bincode::serde::encode_into_std_write(value, writer, config)
+            .map(|_| ())
+            .map_err(Error::EncodeError)
+    }
+
+    #[inline]
+    fn decode<'a, T: serde::Deserialize<'a>>(bytes: &'a [u8]) -> Result<T> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_fixed_int_encoding();
+        bincode::serde::decode_borrowed_from_slice(bytes, config).map_err(Error::DecodeError)
+    }
+}
@@ -0,0 +1,91 @@
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{BincodeStandard, Codec, CompareAndSwapError, Entry, Error, KeyOf, Result, Tree, ValOf, Value};
+
+type TxResult<R> = sled::transaction::TransactionResult<R, Infallible>;
+
+#[inline]
+fn unwrap_transaction<R>(result: TxResult<R>) -> Result<R> {
+    result.map_err(|err| match err {
+        sled::transaction::TransactionError::Abort(infallible) => match infallible {},
+        sled::transaction::TransactionError::Storage(err) => Error::SledError(err),
+    })
+}
+
+/// A [`Tree`] paired with an in-memory counter, giving O(1) [`len`](Self::len)
+/// instead of the O(n) walk `sled::Tree::len` performs.
+///
+/// The counter is kept exact by running every mutation inside a single-tree
+/// transaction and only adjusting the atomic once the transaction has
+/// actually committed, since sled retries the transaction closure on
+/// conflict. This is the technique Garage uses for its counted trees.
+pub struct CountedTree<A, C = BincodeStandard> {
+    tree: Tree<A, C>,
+    count: AtomicU64,
+}
+
+impl<A, C> CountedTree<A, C> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count.load(Ordering::Relaxed) as usize
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<A: for<'a> Entry<'a>, C: Codec> CountedTree<A, C> {
+    pub fn open<S: AsRef<[u8]>>(db: &sled::Db, name: S) -> Result<Self> {
+        let tree = Tree::open(db, name)?;
+        let count = tree.iter().count() as u64;
+        Ok(Self {
+            tree,
+            count: AtomicU64::new(count),
+        })
+    }
+
+    #[inline]
+    pub fn get(&self, key: &KeyOf<A>) -> Result<Option<Value<A, C>>> {
+        self.tree.get(key)
+    }
+
+    pub fn insert(&self, key: &KeyOf<A>, value: &ValOf<A>) -> Result<Option<Value<A, C>>> {
+        let previous = unwrap_transaction(self.tree.transaction(|t| Ok(t.insert(key, value)?)))?;
+        if previous.is_none() {
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(previous)
+    }
+
+    pub fn remove(&self, key: &KeyOf<A>) -> Result<Option<Value<A, C>>> {
+        let removed = unwrap_transaction(self.tree.transaction(|t| Ok(t.remove(key)?)))?;
+        if removed.is_some() {
+            self.count.fetch_sub(1, Ordering::Relaxed);
+        }
+        Ok(removed)
+    }
+
+    pub fn compare_and_swap(
+        &self,
+        key: &KeyOf<A>,
+        old: Option<&ValOf<A>>,
+        new: Option<&ValOf<A>>,
+    ) -> Result<std::result::Result<(), CompareAndSwapError<A, C>>> {
+        let result = unwrap_transaction(self.tree.transaction(|t| Ok(t.compare_and_swap(key, old, new)?)))?;
+        if result.is_ok() {
+            match (old.is_some(), new.is_some()) {
+                (false, true) => {
+                    self.count.fetch_add(1, Ordering::Relaxed);
+                }
+                (true, false) => {
+                    self.count.fetch_sub(1, Ordering::Relaxed);
+                }
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{BincodeStandard, Codec, Entry, KeyOf, Result, Tree, ValOf};
+
+/// A [`Tree`] whose keys are monotonically increasing ids handed out by
+/// [`sled::Db::generate_id`], for the common case where callers don't want to
+/// manage ids by hand.
+pub struct KeyGeneratingTree<A, C = BincodeStandard> {
+    db: sled::Db,
+    tree: Tree<A, C>,
+}
+
+impl<A, C> KeyGeneratingTree<A, C> {
+    pub fn open<S: AsRef<[u8]>>(db: &sled::Db, name: S) -> Result<Self> {
+        let tree = Tree::open(db, name)?;
+        Ok(Self { db: db.clone(), tree })
+    }
+
+    #[inline]
+    pub fn tree(&self) -> &Tree<A, C> {
+        &self.tree
+    }
+}
+
+impl<A, C> KeyGeneratingTree<A, C>
+where
+    A: for<'a> Entry<'a>,
+    C: Codec,
+    for<'a> KeyOf<'a, A>: From<u64>,
+{
+    /// Generates a fresh id via [`sled::Db::generate_id`], inserts `value`
+    /// under it, and returns the typed key that was generated.
+    pub fn insert_generated(&self, value: &ValOf<A>) -> Result<KeyOf<A>> {
+        let id = self.db.generate_id()?;
+        let key = KeyOf::<A>::from(id);
+        self.tree.insert(&key, value)?;
+        Ok(key)
+    }
+}
@@ -1,11 +1,19 @@
 use std::marker::PhantomData;
-use std::ops::RangeBounds;
+use std::ops::{Bound, RangeBounds};
 
 use sled::IVec;
 use smallvec::SmallVec;
 
+mod codec;
+mod counted;
+mod keygen;
 mod result;
+mod subscribe;
+pub use codec::{BincodeStandard, Codec, OrderedKeyCodec};
+pub use counted::CountedTree;
+pub use keygen::KeyGeneratingTree;
 pub use result::{Error, Result};
+pub use subscribe::{Event, Subscriber};
 pub use sled::transaction::{ConflictableTransactionError, TransactionError};
 pub use sled::{open, Db, Error as SledError};
 
@@ -24,12 +32,12 @@ pub trait Entry<'a> {
 type KeyOf<'a, A> = <A as Entry<'a>>::Key;
 type ValOf<'a, A> = <A as Entry<'a>>::Val;
 
-pub struct Tree<A> {
+pub struct Tree<A, C = BincodeStandard> {
     raw: sled::Tree,
-    phantom: PhantomData<A>,
+    phantom: PhantomData<(A, C)>,
 }
 
-impl<A> Tree<A> {
+impl<A, C> Tree<A, C> {
     pub fn open<S: AsRef<[u8]>>(db: &sled::Db, name: S) -> Result<Self> {
         let tree = Self {
             raw: db.open_tree(name)?,
@@ -41,13 +49,26 @@ impl<A> Tree<A> {
     #[inline]
     pub fn transaction<F, R, E>(&self, f: F) -> sled::transaction::TransactionResult<R, E>
     where
-        F: Fn(TransactionalTree<A>) -> sled::transaction::ConflictableTransactionResult<R, E>,
+        F: Fn(TransactionalTree<A, C>) -> sled::transaction::ConflictableTransactionResult<R, E>,
     {
-        self.raw.transaction(|t| f(TransactionalTree::new(t)))
+        let callbacks = CommitCallbacks::default();
+        let result = {
+            let callbacks = callbacks.clone();
+            self.raw.transaction(move |t| {
+                callbacks.borrow_mut().clear();
+                f(TransactionalTree::new(t, callbacks.clone()))
+            })
+        };
+        if result.is_ok() {
+            for callback in callbacks.borrow_mut().drain(..) {
+                callback();
+            }
+        }
+        result
     }
 
     #[inline]
-    pub fn apply_batch(&self, batch: Batch<A>) -> Result<()> {
+    pub fn apply_batch(&self, batch: Batch<A, C>) -> Result<()> {
         Ok(self.raw.apply_batch(batch.raw)?)
     }
 
@@ -57,17 +78,17 @@ impl<A> Tree<A> {
     }
 
     #[inline]
-    pub fn iter(&self) -> Iter<A> {
+    pub fn iter(&self) -> Iter<A, C> {
         Iter::new(self.raw.iter())
     }
 
     #[inline]
-    pub fn pop_max(&self) -> Result<Option<KeyValue<A>>> {
+    pub fn pop_max(&self) -> Result<Option<KeyValue<A, C>>> {
         Ok(self.raw.pop_max()?.map(|(k, v)| KeyValue::new(k, v)))
     }
 
     #[inline]
-    pub fn pop_min(&self) -> Result<Option<KeyValue<A>>> {
+    pub fn pop_min(&self) -> Result<Option<KeyValue<A, C>>> {
         Ok(self.raw.pop_min()?.map(|(k, v)| KeyValue::new(k, v)))
     }
 
@@ -87,63 +108,179 @@ impl<A> Tree<A> {
     }
 }
 
-impl<A: for<'a> Entry<'a>> Tree<A> {
+/// A decoded key/value pair handed to the callback passed to
+/// [`Tree::convert`]. Bundled into one nominal type rather than passed as two
+/// bare `KeyOf`/`ValOf` arguments: a higher-ranked closure whose inputs are
+/// only associated-type projections of the bound lifetime can't have that
+/// lifetime appear in its output (rustc E0582), since the projection doesn't
+/// count as the lifetime "appearing". Naming `'a` directly on this struct's
+/// generics sidesteps it.
+pub struct ConvertEntry<'a, A: Entry<'a>> {
+    pub key: KeyOf<'a, A>,
+    pub value: ValOf<'a, A>,
+}
+
+impl<A: for<'a> Entry<'a>, C: Codec> Tree<A, C> {
     #[inline]
-    pub fn insert(&self, key: &KeyOf<A>, value: &ValOf<A>) -> Result<Option<Value<A>>> {
-        let key = encode(key)?;
-        let val = encode(value)?;
+    pub fn insert(&self, key: &KeyOf<A>, value: &ValOf<A>) -> Result<Option<Value<A, C>>> {
+        let key = encode::<_, C>(key)?;
+        let val = encode::<_, C>(value)?;
         Ok(self.raw.insert(key, val)?.map(Value::new))
     }
 
     #[inline]
-    pub fn get(&self, key: &KeyOf<A>) -> Result<Option<Value<A>>> {
-        Ok(self.raw.get(encode(key)?)?.map(Value::new))
+    pub fn get(&self, key: &KeyOf<A>) -> Result<Option<Value<A, C>>> {
+        Ok(self.raw.get(encode::<_, C>(key)?)?.map(Value::new))
+    }
+
+    #[inline]
+    pub fn remove(&self, key: &KeyOf<A>) -> Result<Option<Value<A, C>>> {
+        Ok(self.raw.remove(encode::<_, C>(key)?)?.map(Value::new))
     }
 
     #[inline]
-    pub fn remove(&self, key: &KeyOf<A>) -> Result<Option<Value<A>>> {
-        Ok(self.raw.remove(encode(key)?)?.map(Value::new))
+    pub fn range<'a, R: RangeBounds<KeyOf<'a, A>>>(&self, range: R) -> Result<Iter<A, C>> {
+        let start = encode_bound::<A, C>(range.start_bound())?;
+        let end = encode_bound::<A, C>(range.end_bound())?;
+        Ok(Iter::new(self.raw.range((start, end))))
     }
 
     #[inline]
-    pub fn range<'a, R: RangeBounds<KeyOf<'a, A>>>(&self, range: R) -> Result<Iter<A>> {
-        let start = encode(range.start_bound())?;
-        let end = encode(range.end_bound())?;
-        Ok(Iter::new(self.raw.range(start..end)))
+    pub fn scan_prefix(&self, prefix: &KeyOf<A>) -> Result<Iter<A, C>> {
+        Ok(Iter::new(self.raw.scan_prefix(encode::<_, C>(prefix)?)))
     }
 
     #[inline]
-    pub fn scan_prefix(&self, prefix: &KeyOf<A>) -> Result<Iter<A>> {
-        Ok(Iter::new(self.raw.scan_prefix(encode(prefix)?)))
+    pub fn watch_prefix(&self, prefix: &KeyOf<A>) -> Result<Subscriber<A, C>> {
+        Ok(Subscriber::new(self.raw.watch_prefix(encode::<_, C>(prefix)?)))
+    }
+
+    /// Streams every entry of this tree through `f` and re-encodes the
+    /// result into `dest`, under `dest`'s own entry type and codec. Useful
+    /// for online migrations when a stored schema or codec changes, since
+    /// the alternative is a hand-rolled iterate-decode-reinsert loop that's
+    /// prone to decode/encode mismatches. Entries are written to `dest` in
+    /// batches rather than one `insert` at a time. Returns the number of
+    /// entries migrated.
+    pub fn convert<B, D>(
+        &self,
+        dest: &Tree<B, D>,
+        f: impl for<'a> Fn(ConvertEntry<'a, A>) -> (KeyOf<'a, B>, ValOf<'a, B>),
+    ) -> Result<usize>
+    where
+        B: for<'a> Entry<'a>,
+        D: Codec,
+    {
+        const CHUNK_SIZE: usize = 1024;
+
+        let mut migrated = 0;
+        let mut batch = Batch::<B, D>::new();
+        let mut pending = 0;
+        for kv in self.iter() {
+            let kv = kv?;
+            let (key, value) = f(ConvertEntry {
+                key: kv.key()?,
+                value: kv.value()?,
+            });
+            batch.insert(&key, &value)?;
+            pending += 1;
+            migrated += 1;
+            if pending == CHUNK_SIZE {
+                dest.apply_batch(std::mem::take(&mut batch))?;
+                pending = 0;
+            }
+        }
+        if pending > 0 {
+            dest.apply_batch(batch)?;
+        }
+        Ok(migrated)
+    }
+
+    #[inline]
+    pub fn compare_and_swap(
+        &self,
+        key: &KeyOf<A>,
+        old: Option<&ValOf<A>>,
+        new: Option<&ValOf<A>>,
+    ) -> Result<std::result::Result<(), CompareAndSwapError<A, C>>> {
+        let key = encode::<_, C>(key)?;
+        let old = old.map(|v| encode::<_, C>(v)).transpose()?;
+        let new = new.map(|v| encode::<_, C>(v)).transpose()?;
+        match self.raw.compare_and_swap(key, old, new)? {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => Ok(Err(CompareAndSwapError {
+                current: err.current.map(Value::new),
+                proposed: err.proposed.map(Value::new),
+            })),
+        }
     }
 }
 
-#[derive(Debug, Default)]
-pub struct Batch<A> {
+/// Returned by [`Tree::compare_and_swap`] and
+/// [`TransactionalTree::compare_and_swap`] when `old` did not match the
+/// value actually stored under `key`.
+pub struct CompareAndSwapError<A, C = BincodeStandard> {
+    pub current: Option<Value<A, C>>,
+    pub proposed: Option<Value<A, C>>,
+}
+
+// `Value<A, C>` has no `Debug` impl (it requires decoding to print), so a
+// derived `Debug` here would never be satisfiable. Report presence only.
+impl<A, C> std::fmt::Debug for CompareAndSwapError<A, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompareAndSwapError")
+            .field("current", &self.current.is_some())
+            .field("proposed", &self.proposed.is_some())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct Batch<A, C = BincodeStandard> {
     raw: sled::Batch,
-    phantom: PhantomData<A>,
+    phantom: PhantomData<(A, C)>,
+}
+
+impl<A, C> Batch<A, C> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            raw: sled::Batch::default(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+// A derived `Default` would require `A: Default, C: Default` even though
+// neither is ever used to construct a value, since `#[derive]` adds bounds
+// per generic parameter rather than per field.
+impl<A, C> Default for Batch<A, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<A: for<'a> Entry<'a>> Batch<A> {
+impl<A: for<'a> Entry<'a>, C: Codec> Batch<A, C> {
     #[inline]
     pub fn insert(&mut self, key: &KeyOf<A>, val: &ValOf<A>) -> Result<()> {
-        self.raw.insert(encode(key)?, encode(val)?.as_ref());
+        self.raw.insert(encode::<_, C>(key)?, encode::<_, C>(val)?.as_ref());
         Ok(())
     }
 
     #[inline]
     pub fn remove(&mut self, key: &KeyOf<A>) -> Result<()> {
-        self.raw.remove(encode(key)?);
+        self.raw.remove(encode::<_, C>(key)?);
         Ok(())
     }
 }
 
-pub struct Value<A> {
+pub struct Value<A, C = BincodeStandard> {
     raw: sled::IVec,
-    phantom: PhantomData<A>,
+    phantom: PhantomData<(A, C)>,
 }
 
-impl<A> Value<A> {
+impl<A, C> Value<A, C> {
     #[inline]
     fn new(raw: sled::IVec) -> Self {
         Self {
@@ -153,17 +290,18 @@ impl<A> Value<A> {
     }
 }
 
-impl<A: for<'a> Entry<'a>> Value<A> {
+impl<A: for<'a> Entry<'a>, C: Codec> Value<A, C> {
     #[inline]
     pub fn value(&self) -> Result<ValOf<A>> {
-        decode(&self.raw)
+        decode::<_, C>(&self.raw)
     }
 }
 
 #[cfg(feature = "serde")]
-impl<A> serde::Serialize for Value<A>
+impl<A, C> serde::Serialize for Value<A, C>
 where
     A: for<'a> Entry<'a>,
+    C: Codec,
     for<'a> ValOf<'a, A>: serde::Serialize,
 {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -172,12 +310,12 @@ where
     }
 }
 
-pub struct Key<A> {
+pub struct Key<A, C = BincodeStandard> {
     raw: sled::IVec,
-    phantom: PhantomData<A>,
+    phantom: PhantomData<(A, C)>,
 }
 
-impl<A> Key<A> {
+impl<A, C> Key<A, C> {
     #[inline]
     fn new(raw: sled::IVec) -> Self {
         Self {
@@ -187,17 +325,18 @@ impl<A> Key<A> {
     }
 }
 
-impl<A: for<'a> Entry<'a>> Key<A> {
+impl<A: for<'a> Entry<'a>, C: Codec> Key<A, C> {
     #[inline]
     pub fn key(&self) -> Result<KeyOf<A>> {
-        decode(&self.raw)
+        decode::<_, C>(&self.raw)
     }
 }
 
 #[cfg(feature = "serde")]
-impl<A> serde::Serialize for Key<A>
+impl<A, C> serde::Serialize for Key<A, C>
 where
     A: for<'a> Entry<'a>,
+    C: Codec,
     for<'a> KeyOf<'a, A>: serde::Serialize,
 {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -206,13 +345,13 @@ where
     }
 }
 
-pub struct KeyValue<A> {
+pub struct KeyValue<A, C = BincodeStandard> {
     raw_key: sled::IVec,
     raw_value: sled::IVec,
-    phantom: PhantomData<A>,
+    phantom: PhantomData<(A, C)>,
 }
 
-impl<A> KeyValue<A> {
+impl<A, C> KeyValue<A, C> {
     #[inline]
     fn new(raw_key: sled::IVec, raw_value: sled::IVec) -> Self {
         Self {
@@ -223,46 +362,57 @@ impl<A> KeyValue<A> {
     }
 
     #[inline]
-    pub fn into_key(self) -> Key<A> {
+    pub fn into_key(self) -> Key<A, C> {
         Key::new(self.raw_value)
     }
 
     #[inline]
-    pub fn into_value(self) -> Value<A> {
+    pub fn into_value(self) -> Value<A, C> {
         Value::new(self.raw_value)
     }
 }
 
-impl<A: for<'a> Entry<'a>> KeyValue<A> {
+impl<A: for<'a> Entry<'a>, C: Codec> KeyValue<A, C> {
     #[inline]
     pub fn key(&self) -> Result<KeyOf<A>> {
-        decode(&self.raw_key)
+        decode::<_, C>(&self.raw_key)
     }
 
     #[inline]
     pub fn value(&self) -> Result<ValOf<A>> {
-        decode(&self.raw_value)
+        decode::<_, C>(&self.raw_value)
     }
 }
 
 type TransactionalResult<A> = Result<A, sled::transaction::UnabortableTransactionError>;
 
-pub struct TransactionalTree<'a, A> {
+/// Per-attempt buffer of callbacks registered via
+/// [`TransactionalTree::on_commit`]. Shared (via `Rc`) between every attempt
+/// sled makes at a transaction, cleared at the start of each attempt, and
+/// drained only once the surrounding transaction commits. It is
+/// cloned into the closure sled invokes rather than borrowed, since a
+/// borrowed buffer cannot be proven to outlive that closure while still
+/// being drained afterwards.
+type CommitCallbacks = std::rc::Rc<std::cell::RefCell<Vec<Box<dyn FnOnce()>>>>;
+
+pub struct TransactionalTree<'a, A, C = BincodeStandard> {
     raw: &'a sled::transaction::TransactionalTree,
-    phantom: PhantomData<A>,
+    callbacks: CommitCallbacks,
+    phantom: PhantomData<(A, C)>,
 }
 
-impl<'a, A> TransactionalTree<'a, A> {
+impl<'a, A, C> TransactionalTree<'a, A, C> {
     #[inline]
-    fn new(raw: &'a sled::transaction::TransactionalTree) -> Self {
+    fn new(raw: &'a sled::transaction::TransactionalTree, callbacks: CommitCallbacks) -> Self {
         Self {
             raw,
+            callbacks,
             phantom: PhantomData,
         }
     }
 
     #[inline]
-    pub fn apply_batch(&self, batch: &Batch<A>) -> TransactionalResult<()> {
+    pub fn apply_batch(&self, batch: &Batch<A, C>) -> TransactionalResult<()> {
         self.raw.apply_batch(&batch.raw)
     }
 
@@ -275,24 +425,74 @@ impl<'a, A> TransactionalTree<'a, A> {
     pub fn generate_id(&self) -> Result<u64> {
         Ok(self.raw.generate_id()?)
     }
+
+    /// Registers a callback to run exactly once, after the transaction this
+    /// tree participates in commits (not necessarily flushed to disk yet,
+    /// unless the transaction called [`flush`](Self::flush)). Running side effects directly
+    /// in the transaction closure is unsafe since sled retries it on
+    /// conflict; `on_commit` is the supported way to defer them. The
+    /// callback must be `'static` since it may run after this
+    /// `TransactionalTree` (and the borrows it was built from) are gone.
+    #[inline]
+    pub fn on_commit(&self, callback: impl FnOnce() + 'static) {
+        self.callbacks.borrow_mut().push(Box::new(callback));
+    }
 }
 
-impl<'a, A: for<'v> Entry<'v>> TransactionalTree<'a, A> {
-    pub fn insert(&self, key: &KeyOf<A>, val: &ValOf<A>) -> TransactionalResult<Option<Value<A>>> {
-        let key = encode(key).expect("key encoding failed");
-        let val = encode(val).expect("value encoding failed");
+impl<'a, A: for<'v> Entry<'v>, C: Codec> TransactionalTree<'a, A, C> {
+    pub fn insert(&self, key: &KeyOf<A>, val: &ValOf<A>) -> TransactionalResult<Option<Value<A, C>>> {
+        let key = encode::<_, C>(key).expect("key encoding failed");
+        let val = encode::<_, C>(val).expect("value encoding failed");
         Ok(self.raw.insert(key, val)?.map(Value::new))
     }
 
-    pub fn remove(&self, key: &KeyOf<A>) -> TransactionalResult<Option<Value<A>>> {
-        let key = encode(key).expect("key encoding failed");
+    pub fn remove(&self, key: &KeyOf<A>) -> TransactionalResult<Option<Value<A, C>>> {
+        let key = encode::<_, C>(key).expect("key encoding failed");
         Ok(self.raw.remove(key)?.map(Value::new))
     }
 
-    pub fn get(&self, key: &KeyOf<A>) -> TransactionalResult<Option<Value<A>>> {
-        let key = encode(key).expect("key encoding failed");
+    pub fn get(&self, key: &KeyOf<A>) -> TransactionalResult<Option<Value<A, C>>> {
+        let key = encode::<_, C>(key).expect("key encoding failed");
         Ok(self.raw.get(key)?.map(Value::new))
     }
+
+    /// `sled::transaction::TransactionalTree` has no native `compare_and_swap`
+    /// (that primitive only exists on the non-transactional `Tree`), so this
+    /// reimplements it as a `get` followed by a conditional `insert`/`remove`
+    /// within the same transaction.
+    pub fn compare_and_swap(
+        &self,
+        key: &KeyOf<A>,
+        old: Option<&ValOf<A>>,
+        new: Option<&ValOf<A>>,
+    ) -> TransactionalResult<std::result::Result<(), CompareAndSwapError<A, C>>> {
+        let key: IVec = encode::<_, C>(key).expect("key encoding failed").into();
+        let old: Option<IVec> = old.map(|v| encode::<_, C>(v).expect("value encoding failed").into());
+        let new: Option<IVec> = new.map(|v| encode::<_, C>(v).expect("value encoding failed").into());
+
+        let current = self.raw.get(key.clone())?;
+        let matches = match (&current, &old) {
+            (Some(current), Some(old)) => current == old,
+            (None, None) => true,
+            _ => false,
+        };
+        if !matches {
+            return Ok(Err(CompareAndSwapError {
+                current: current.map(Value::new),
+                proposed: new.map(Value::new),
+            }));
+        }
+
+        match new {
+            Some(new) => {
+                self.raw.insert(key, new)?;
+            }
+            None => {
+                self.raw.remove(key)?;
+            }
+        }
+        Ok(Ok(()))
+    }
 }
 
 pub trait Transactional<F, R, E> {
@@ -302,15 +502,28 @@ pub trait Transactional<F, R, E> {
 macro_rules! impl_transactable {
     ($($ty:ident),*) => {
         #[allow(non_snake_case)]
-        impl<$($ty,)* Fun, Res, Err> Transactional<Fun, Res, Err> for ($(&Tree<$ty>),*)
+        impl<$($ty,)* Fun, Res, Err, Cod> Transactional<Fun, Res, Err> for ($(&Tree<$ty, Cod>),*)
         where
-            Fun: Fn($(TransactionalTree<$ty>),*) -> sled::transaction::ConflictableTransactionResult<Res, Err>,
+            Fun: Fn($(TransactionalTree<$ty, Cod>),*) -> sled::transaction::ConflictableTransactionResult<Res, Err>,
         {
             #[inline]
             fn transaction(self, fun: Fun) -> sled::transaction::TransactionResult<Res, Err> {
                 use sled::Transactional;
                 let ($($ty,)*) = self;
-                ($(&$ty.raw),*).transaction(|($($ty),*)| fun($(TransactionalTree::new($ty)),*))
+                let callbacks = CommitCallbacks::default();
+                let result = {
+                    let callbacks = callbacks.clone();
+                    ($(&$ty.raw),*).transaction(move |($($ty),*)| {
+                        callbacks.borrow_mut().clear();
+                        fun($(TransactionalTree::new($ty, callbacks.clone())),*)
+                    })
+                };
+                if result.is_ok() {
+                    for callback in callbacks.borrow_mut().drain(..) {
+                        callback();
+                    }
+                }
+                result
             }
         }
     };
@@ -323,12 +536,12 @@ impl_transactable!(A, B, C, D, E);
 impl_transactable!(A, B, C, D, E, F);
 impl_transactable!(A, B, C, D, E, F, G);
 
-pub struct Iter<A> {
+pub struct Iter<A, C = BincodeStandard> {
     raw: sled::Iter,
-    phantom: PhantomData<A>,
+    phantom: PhantomData<(A, C)>,
 }
 
-impl<A> Iter<A> {
+impl<A, C> Iter<A, C> {
     #[inline]
     fn new(raw: sled::Iter) -> Self {
         Self {
@@ -338,7 +551,7 @@ impl<A> Iter<A> {
     }
 
     #[inline]
-    pub fn keys(self) -> impl DoubleEndedIterator<Item = Result<Key<A>>> {
+    pub fn keys(self) -> impl DoubleEndedIterator<Item = Result<Key<A, C>>> {
         self.raw.map(|r| {
             let (k, _) = r?;
             Ok(Key::new(k))
@@ -346,7 +559,7 @@ impl<A> Iter<A> {
     }
 
     #[inline]
-    pub fn values(self) -> impl DoubleEndedIterator<Item = Result<Value<A>>> {
+    pub fn values(self) -> impl DoubleEndedIterator<Item = Result<Value<A, C>>> {
         self.raw.map(|r| {
             let (_, v) = r?;
             Ok(Value::new(v))
@@ -354,8 +567,8 @@ impl<A> Iter<A> {
     }
 }
 
-impl<A> Iterator for Iter<A> {
-    type Item = Result<KeyValue<A>>;
+impl<A, C> Iterator for Iter<A, C> {
+    type Item = Result<KeyValue<A, C>>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
@@ -366,7 +579,7 @@ impl<A> Iterator for Iter<A> {
     }
 }
 
-impl<A> DoubleEndedIterator for Iter<A> {
+impl<A, C> DoubleEndedIterator for Iter<A, C> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
         self.raw.next_back().map(|res| {
@@ -396,34 +609,44 @@ impl AsRef<[u8]> for Buffer {
     }
 }
 
+/// Encodes the key inside a [`Bound`], leaving the variant (and thus the
+/// inclusive/exclusive/unbounded semantics) untouched. `Tree::range` must
+/// encode only the bare key bytes here: encoding the `Bound` itself would
+/// serialize its discriminant alongside the key, which never matches the
+/// bare-key bytes `Tree::insert` writes.
+#[inline]
+fn encode_bound<'a, 'b, A: Entry<'a>, C: Codec>(bound: Bound<&'b KeyOf<'a, A>>) -> Result<Bound<IVec>> {
+    Ok(match bound {
+        Bound::Included(key) => Bound::Included(encode::<_, C>(key)?.into()),
+        Bound::Excluded(key) => Bound::Excluded(encode::<_, C>(key)?.into()),
+        Bound::Unbounded => Bound::Unbounded,
+    })
+}
+
 #[cfg(not(feature = "serde"))]
 #[inline]
-fn decode<'a, A: bincode::BorrowDecode<'a>>(buf: &'a [u8]) -> Result<A> {
-    let (val, _) =
-        bincode::decode_from_slice(buf, bincode::config::standard()).map_err(Error::DecodeError)?;
-    Ok(val)
+fn decode<'a, A: bincode::BorrowDecode<'a>, C: Codec>(buf: &'a [u8]) -> Result<A> {
+    C::decode(buf)
 }
 
 #[cfg(not(feature = "serde"))]
 #[inline]
-fn encode<A: bincode::Encode>(val: A) -> Result<Buffer> {
+fn encode<A: bincode::Encode, C: Codec>(val: A) -> Result<Buffer> {
     let mut vec = SmallVec::new();
-    bincode::encode_into_std_write(val, &mut vec, bincode::config::standard())
-        .map_err(Error::EncodeError)?;
+    C::encode_into(&val, &mut vec)?;
     Ok(Buffer(vec))
 }
 
 #[cfg(feature = "serde")]
 #[inline]
-fn decode<'a, A: serde::Deserialize<'a>>(buf: &'a [u8]) -> Result<A> {
-    bincode::serde::decode_borrowed_from_slice(buf, bincode::config::standard()).map_err(Error::DecodeError)
+fn decode<'a, A: serde::Deserialize<'a>, C: Codec>(buf: &'a [u8]) -> Result<A> {
+    C::decode(buf)
 }
 
 #[cfg(feature = "serde")]
 #[inline]
-fn encode<A: serde::Serialize>(val: A) -> Result<Buffer> {
+fn encode<A: serde::Serialize, C: Codec>(val: A) -> Result<Buffer> {
     let mut vec = SmallVec::new();
-    bincode::serde::encode_into_std_write(val, &mut vec, bincode::config::standard())
-        .map_err(Error::EncodeError)?;
+    C::encode_into(&val, &mut vec)?;
     Ok(Buffer(vec))
 }
@@ -0,0 +1,63 @@
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{BincodeStandard, Key, Value};
+
+/// A typed counterpart to `sled::Event`, carrying decodable keys/values
+/// instead of raw bytes.
+pub enum Event<A, C = BincodeStandard> {
+    Insert { key: Key<A, C>, value: Value<A, C> },
+    Remove { key: Key<A, C> },
+}
+
+impl<A, C> Event<A, C> {
+    fn from_raw(raw: sled::Event) -> Self {
+        match raw {
+            sled::Event::Insert { key, value } => Event::Insert {
+                key: Key::new(key),
+                value: Value::new(value),
+            },
+            sled::Event::Remove { key } => Event::Remove { key: Key::new(key) },
+        }
+    }
+}
+
+/// A typed wrapper around `sled::Subscriber`, returned by
+/// [`Tree::watch_prefix`](crate::Tree::watch_prefix). Like the underlying
+/// sled type, it can be driven either as a blocking [`Iterator`] or awaited
+/// as a [`Future`](std::future::Future) in async contexts, mirroring how
+/// [`Tree::flush_async`](crate::Tree::flush_async) bridges sled's async API.
+pub struct Subscriber<A, C = BincodeStandard> {
+    raw: sled::Subscriber,
+    phantom: PhantomData<(A, C)>,
+}
+
+impl<A, C> Subscriber<A, C> {
+    #[inline]
+    pub(crate) fn new(raw: sled::Subscriber) -> Self {
+        Self {
+            raw,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A, C> Iterator for Subscriber<A, C> {
+    type Item = Event<A, C>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next().map(Event::from_raw)
+    }
+}
+
+impl<A, C> std::future::Future for Subscriber<A, C> {
+    type Output = Option<Event<A, C>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let raw = unsafe { self.map_unchecked_mut(|s| &mut s.raw) };
+        raw.poll(cx).map(|event| event.map(Event::from_raw))
+    }
+}
@@ -1,5 +1,7 @@
 use bincode::{BorrowDecode, Encode};
-use sled_bincode::{ConflictableTransactionError, Transactional, Tree, TreeEntry};
+use sled_bincode::{
+    ConflictableTransactionError, CountedTree, Entry, Event, KeyGeneratingTree, OrderedKeyCodec, Transactional, Tree,
+};
 use temp_dir::TempDir;
 
 #[derive(Debug, PartialEq, BorrowDecode, Encode)]
@@ -10,7 +12,7 @@ struct Person<'a> {
 
 struct PersonEntry;
 
-impl<'a> TreeEntry<'a> for PersonEntry {
+impl<'a> Entry<'a> for PersonEntry {
     type Key = &'a str;
     type Val = Person<'a>;
 }
@@ -21,6 +23,16 @@ fn test_tree() -> Tree<PersonEntry> {
     Tree::open(&db, "people").unwrap()
 }
 
+#[derive(Debug, PartialEq, BorrowDecode, Encode)]
+struct Counter(u64);
+
+struct CounterEntry;
+
+impl<'a> Entry<'a> for CounterEntry {
+    type Key = u64;
+    type Val = Counter;
+}
+
 #[test]
 fn insert_and_get_works() {
     let tree = test_tree();
@@ -59,6 +71,236 @@ fn iter_works() {
     assert_eq!(kv.value().unwrap(), person);
 }
 
+#[test]
+fn ordered_key_codec_preserves_numeric_order() {
+    let dir = TempDir::new().unwrap();
+    let db = sled::open(dir.path()).unwrap();
+    let tree: Tree<CounterEntry, OrderedKeyCodec> = Tree::open(&db, "counters").unwrap();
+
+    tree.insert(&256, &Counter(256)).unwrap();
+    tree.insert(&1, &Counter(1)).unwrap();
+    tree.insert(&2, &Counter(2)).unwrap();
+
+    let keys: Vec<u64> = tree.iter().map(|kv| kv.unwrap().key().unwrap()).collect();
+    assert_eq!(keys, vec![1, 2, 256]);
+}
+
+#[test]
+fn ordered_key_codec_range_and_scan_prefix_are_ordered() {
+    let dir = TempDir::new().unwrap();
+    let db = sled::open(dir.path()).unwrap();
+    let tree: Tree<CounterEntry, OrderedKeyCodec> = Tree::open(&db, "counters-range").unwrap();
+
+    for n in [256u64, 1, 2, 128, 3] {
+        tree.insert(&n, &Counter(n)).unwrap();
+    }
+
+    let ranged: Vec<u64> = tree
+        .range(2..128)
+        .unwrap()
+        .map(|kv| kv.unwrap().key().unwrap())
+        .collect();
+    assert_eq!(ranged, vec![2, 3]);
+
+    let from_three: Vec<u64> = tree
+        .range(3..)
+        .unwrap()
+        .map(|kv| kv.unwrap().key().unwrap())
+        .collect();
+    assert_eq!(from_three, vec![3, 128, 256]);
+
+    let prefixed: Vec<u64> = tree
+        .scan_prefix(&1)
+        .unwrap()
+        .map(|kv| kv.unwrap().key().unwrap())
+        .collect();
+    assert_eq!(prefixed, vec![1]);
+}
+
+#[test]
+fn compare_and_swap_works() {
+    let tree = test_tree();
+    let person = Person {
+        name: "John",
+        age: 32,
+    };
+    tree.insert(&person.name, &person).unwrap();
+
+    let older = Person {
+        name: "John",
+        age: 33,
+    };
+    tree.compare_and_swap(&person.name, Some(&person), Some(&older))
+        .unwrap()
+        .unwrap();
+    assert_eq!(tree.get(&person.name).unwrap().unwrap().value().unwrap(), older);
+
+    let stale = Person {
+        name: "John",
+        age: 1,
+    };
+    let err = tree
+        .compare_and_swap(&person.name, Some(&person), Some(&stale))
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.current.unwrap().value().unwrap(), older);
+    assert_eq!(tree.get(&person.name).unwrap().unwrap().value().unwrap(), older);
+}
+
+#[test]
+fn transactional_compare_and_swap_works() {
+    let tree = test_tree();
+    let person = Person {
+        name: "John",
+        age: 32,
+    };
+    tree.insert(&person.name, &person).unwrap();
+
+    let older = Person {
+        name: "John",
+        age: 33,
+    };
+    tree.transaction(|t| {
+        t.compare_and_swap(&person.name, Some(&person), Some(&older))?
+            .expect("cas should have matched");
+        Ok::<_, ConflictableTransactionError>(())
+    })
+    .unwrap();
+
+    assert_eq!(tree.get(&person.name).unwrap().unwrap().value().unwrap(), older);
+}
+
+#[test]
+fn counted_tree_tracks_len_through_mutations() {
+    let dir = TempDir::new().unwrap();
+    let db = sled::open(dir.path()).unwrap();
+    let tree: CountedTree<PersonEntry> = CountedTree::open(&db, "counted").unwrap();
+    assert_eq!(tree.len(), 0);
+    assert!(tree.is_empty());
+
+    let person = Person {
+        name: "John",
+        age: 32,
+    };
+    tree.insert(&person.name, &person).unwrap();
+    assert_eq!(tree.len(), 1);
+
+    // Re-inserting under the same key must not double count.
+    tree.insert(&person.name, &person).unwrap();
+    assert_eq!(tree.len(), 1);
+
+    tree.insert(&"Paul", &person).unwrap();
+    assert_eq!(tree.len(), 2);
+
+    tree.remove(&person.name).unwrap();
+    assert_eq!(tree.len(), 1);
+
+    tree.remove(&"Paul").unwrap();
+    assert_eq!(tree.len(), 0);
+    assert!(tree.is_empty());
+}
+
+#[test]
+fn counted_tree_compare_and_swap_adjusts_len() {
+    let dir = TempDir::new().unwrap();
+    let db = sled::open(dir.path()).unwrap();
+    let tree: CountedTree<PersonEntry> = CountedTree::open(&db, "counted-cas").unwrap();
+
+    let person = Person {
+        name: "John",
+        age: 32,
+    };
+    tree.compare_and_swap(&person.name, None, Some(&person)).unwrap().unwrap();
+    assert_eq!(tree.len(), 1);
+
+    tree.compare_and_swap(&person.name, Some(&person), None).unwrap().unwrap();
+    assert_eq!(tree.len(), 0);
+}
+
+#[test]
+fn key_generating_tree_assigns_distinct_ids() {
+    let dir = TempDir::new().unwrap();
+    let db = sled::open(dir.path()).unwrap();
+    let tree: KeyGeneratingTree<CounterEntry> = KeyGeneratingTree::open(&db, "ids").unwrap();
+
+    let first = tree.insert_generated(&Counter(10)).unwrap();
+    let second = tree.insert_generated(&Counter(20)).unwrap();
+
+    assert_ne!(first, second);
+    assert_eq!(tree.tree().get(&first).unwrap().unwrap().value().unwrap(), Counter(10));
+    assert_eq!(tree.tree().get(&second).unwrap().unwrap().value().unwrap(), Counter(20));
+}
+
+#[test]
+fn on_commit_runs_once_after_a_successful_transaction() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let tree = test_tree();
+    let person = Person {
+        name: "John",
+        age: 32,
+    };
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    tree.transaction(|t| {
+        t.insert(&person.name, &person)?;
+        let calls = calls.clone();
+        t.on_commit(move || {
+            calls.fetch_add(1, Ordering::SeqCst);
+        });
+        Ok::<_, ConflictableTransactionError>(())
+    })
+    .unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn watch_prefix_yields_typed_events() {
+    let tree = test_tree();
+    let mut subscriber = tree.watch_prefix(&"J").unwrap();
+
+    let person = Person {
+        name: "John",
+        age: 32,
+    };
+    tree.insert(&person.name, &person).unwrap();
+
+    match subscriber.next().unwrap() {
+        Event::Insert { key, value } => {
+            assert_eq!(key.key().unwrap(), "John");
+            assert_eq!(value.value().unwrap(), person);
+        }
+        Event::Remove { .. } => panic!("expected an insert event"),
+    }
+
+    tree.remove(&person.name).unwrap();
+    match subscriber.next().unwrap() {
+        Event::Remove { key } => assert_eq!(key.key().unwrap(), "John"),
+        Event::Insert { .. } => panic!("expected a remove event"),
+    }
+}
+
+#[test]
+fn convert_migrates_every_entry_under_a_new_codec() {
+    let dir = TempDir::new().unwrap();
+    let db = sled::open(dir.path()).unwrap();
+    let source: Tree<CounterEntry> = Tree::open(&db, "counters-v1").unwrap();
+    let dest: Tree<CounterEntry, OrderedKeyCodec> = Tree::open(&db, "counters-v2").unwrap();
+
+    source.insert(&1, &Counter(10)).unwrap();
+    source.insert(&2, &Counter(20)).unwrap();
+    source.insert(&3, &Counter(30)).unwrap();
+
+    let migrated = source.convert(&dest, |entry| (entry.key, entry.value)).unwrap();
+    assert_eq!(migrated, 3);
+
+    for key in 1..=3u64 {
+        assert_eq!(dest.get(&key).unwrap().unwrap().value().unwrap(), Counter(key * 10));
+    }
+}
+
 #[test]
 fn transaction_works() {
     let dir = TempDir::new().unwrap();